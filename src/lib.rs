@@ -1,12 +1,37 @@
 #[cfg(feature = "use-hyper")]
 extern crate hyper;
+#[cfg(feature = "use-futures")]
+extern crate futures;
+#[cfg(feature = "use-futures")]
+extern crate tokio_io;
+#[cfg(any(feature = "use-serde-json", feature = "use-serde-form"))]
+extern crate serde;
+#[cfg(all(test, any(feature = "use-serde-json", feature = "use-serde-form")))]
+#[macro_use]
+extern crate serde_derive;
+#[cfg(feature = "use-serde-json")]
+extern crate serde_json;
+#[cfg(feature = "use-serde-form")]
+extern crate serde_urlencoded;
 
 /// *api* is a library that abstracts a HTTP API
 /// and separates the client from the API definition.
 /// This allows you to change the underlying HTTP
 /// client easily.
 use std::io;
-use std::collections::BTreeMap;
+use std::io::Read;
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{OnceLock, RwLock};
+use std::thread;
+use std::time::Duration;
+#[cfg(feature = "use-futures")]
+use futures::Future;
+#[cfg(feature = "use-futures")]
+use tokio_io::AsyncRead;
+#[cfg(any(feature = "use-serde-json", feature = "use-serde-form"))]
+use serde::Serialize;
 
 
 /// Type for the request/response headers.
@@ -14,8 +39,49 @@ pub type Headers = BTreeMap<String, Vec<String>>;
 /// Type for the URL query.
 pub type Query<'s> = Vec<(String, String)>;
 
+/// A typemap keyed by `TypeId`, carried alongside a request or
+/// response so middleware-style code can stash typed values.
+#[derive(Default)]
+pub struct Extensions {
+    map: HashMap<TypeId, Box<Any + Send + Sync>>,
+}
+
+impl Extensions {
+    pub fn new() -> Extensions {
+        Extensions { map: HashMap::new() }
+    }
+
+    /// Insert `value`, returning whatever was previously stored under
+    /// the same type, if any.
+    pub fn insert<T: Any + Send + Sync>(&mut self, value: T) -> Option<T> {
+        self.map.insert(TypeId::of::<T>(), Box::new(value))
+            .and_then(|prev| prev.downcast().ok())
+            .map(|prev| *prev)
+    }
+
+    pub fn get<T: Any>(&self) -> Option<&T> {
+        self.map.get(&TypeId::of::<T>()).and_then(|v| v.downcast_ref())
+    }
+
+    pub fn get_mut<T: Any>(&mut self) -> Option<&mut T> {
+        self.map.get_mut(&TypeId::of::<T>()).and_then(|v| v.downcast_mut())
+    }
+
+    pub fn remove<T: Any>(&mut self) -> Option<T> {
+        self.map.remove(&TypeId::of::<T>())
+            .and_then(|v| v.downcast().ok())
+            .map(|v| *v)
+    }
+
+    /// Return `true` if nothing has been inserted into this map.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
 /// Enum with all the standard HTTP methods. It also has
 /// a variant `Custom` to support non-standard methods.
+#[derive(Clone)]
 pub enum Method {
     Get,
     Head,
@@ -84,6 +150,309 @@ impl Into<hyper::method::Method> for Method {
 }
 
 
+/// Enum with all the standard HTTP status codes. `Custom` covers
+/// anything non-standard (or standard codes this crate hasn't named
+/// yet), so matching on a response's status never has to fall back to
+/// a bare, misspelling-prone `u16`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusCode {
+    Continue,
+    SwitchingProtocols,
+    Processing,
+    Ok,
+    Created,
+    Accepted,
+    NonAuthoritativeInformation,
+    NoContent,
+    ResetContent,
+    PartialContent,
+    MultiStatus,
+    AlreadyReported,
+    ImUsed,
+    MultipleChoices,
+    MovedPermanently,
+    Found,
+    SeeOther,
+    NotModified,
+    UseProxy,
+    TemporaryRedirect,
+    PermanentRedirect,
+    BadRequest,
+    Unauthorized,
+    PaymentRequired,
+    Forbidden,
+    NotFound,
+    MethodNotAllowed,
+    NotAcceptable,
+    ProxyAuthenticationRequired,
+    RequestTimeout,
+    Conflict,
+    Gone,
+    LengthRequired,
+    PreconditionFailed,
+    PayloadTooLarge,
+    UriTooLong,
+    UnsupportedMediaType,
+    RangeNotSatisfiable,
+    ExpectationFailed,
+    ImATeapot,
+    UnprocessableEntity,
+    Locked,
+    FailedDependency,
+    UpgradeRequired,
+    PreconditionRequired,
+    TooManyRequests,
+    RequestHeaderFieldsTooLarge,
+    UnavailableForLegalReasons,
+    InternalServerError,
+    NotImplemented,
+    BadGateway,
+    ServiceUnavailable,
+    GatewayTimeout,
+    HttpVersionNotSupported,
+    VariantAlsoNegotiates,
+    InsufficientStorage,
+    LoopDetected,
+    NotExtended,
+    NetworkAuthenticationRequired,
+    Custom(u16),
+}
+
+impl StatusCode {
+    /// Return the numeric status code, e.g. `404`.
+    pub fn as_u16(&self) -> u16 {
+        match *self {
+            StatusCode::Continue => 100,
+            StatusCode::SwitchingProtocols => 101,
+            StatusCode::Processing => 102,
+            StatusCode::Ok => 200,
+            StatusCode::Created => 201,
+            StatusCode::Accepted => 202,
+            StatusCode::NonAuthoritativeInformation => 203,
+            StatusCode::NoContent => 204,
+            StatusCode::ResetContent => 205,
+            StatusCode::PartialContent => 206,
+            StatusCode::MultiStatus => 207,
+            StatusCode::AlreadyReported => 208,
+            StatusCode::ImUsed => 226,
+            StatusCode::MultipleChoices => 300,
+            StatusCode::MovedPermanently => 301,
+            StatusCode::Found => 302,
+            StatusCode::SeeOther => 303,
+            StatusCode::NotModified => 304,
+            StatusCode::UseProxy => 305,
+            StatusCode::TemporaryRedirect => 307,
+            StatusCode::PermanentRedirect => 308,
+            StatusCode::BadRequest => 400,
+            StatusCode::Unauthorized => 401,
+            StatusCode::PaymentRequired => 402,
+            StatusCode::Forbidden => 403,
+            StatusCode::NotFound => 404,
+            StatusCode::MethodNotAllowed => 405,
+            StatusCode::NotAcceptable => 406,
+            StatusCode::ProxyAuthenticationRequired => 407,
+            StatusCode::RequestTimeout => 408,
+            StatusCode::Conflict => 409,
+            StatusCode::Gone => 410,
+            StatusCode::LengthRequired => 411,
+            StatusCode::PreconditionFailed => 412,
+            StatusCode::PayloadTooLarge => 413,
+            StatusCode::UriTooLong => 414,
+            StatusCode::UnsupportedMediaType => 415,
+            StatusCode::RangeNotSatisfiable => 416,
+            StatusCode::ExpectationFailed => 417,
+            StatusCode::ImATeapot => 418,
+            StatusCode::UnprocessableEntity => 422,
+            StatusCode::Locked => 423,
+            StatusCode::FailedDependency => 424,
+            StatusCode::UpgradeRequired => 426,
+            StatusCode::PreconditionRequired => 428,
+            StatusCode::TooManyRequests => 429,
+            StatusCode::RequestHeaderFieldsTooLarge => 431,
+            StatusCode::UnavailableForLegalReasons => 451,
+            StatusCode::InternalServerError => 500,
+            StatusCode::NotImplemented => 501,
+            StatusCode::BadGateway => 502,
+            StatusCode::ServiceUnavailable => 503,
+            StatusCode::GatewayTimeout => 504,
+            StatusCode::HttpVersionNotSupported => 505,
+            StatusCode::VariantAlsoNegotiates => 506,
+            StatusCode::InsufficientStorage => 507,
+            StatusCode::LoopDetected => 508,
+            StatusCode::NotExtended => 510,
+            StatusCode::NetworkAuthenticationRequired => 511,
+            StatusCode::Custom(code) => code,
+        }
+    }
+
+    /// Return the canonical reason-phrase for this status code, e.g.
+    /// `"Not Found"` for 404. Returns an empty string for `Custom`.
+    pub fn canonical_reason(&self) -> &'static str {
+        match *self {
+            StatusCode::Continue => "Continue",
+            StatusCode::SwitchingProtocols => "Switching Protocols",
+            StatusCode::Processing => "Processing",
+            StatusCode::Ok => "OK",
+            StatusCode::Created => "Created",
+            StatusCode::Accepted => "Accepted",
+            StatusCode::NonAuthoritativeInformation => "Non-Authoritative Information",
+            StatusCode::NoContent => "No Content",
+            StatusCode::ResetContent => "Reset Content",
+            StatusCode::PartialContent => "Partial Content",
+            StatusCode::MultiStatus => "Multi-Status",
+            StatusCode::AlreadyReported => "Already Reported",
+            StatusCode::ImUsed => "IM Used",
+            StatusCode::MultipleChoices => "Multiple Choices",
+            StatusCode::MovedPermanently => "Moved Permanently",
+            StatusCode::Found => "Found",
+            StatusCode::SeeOther => "See Other",
+            StatusCode::NotModified => "Not Modified",
+            StatusCode::UseProxy => "Use Proxy",
+            StatusCode::TemporaryRedirect => "Temporary Redirect",
+            StatusCode::PermanentRedirect => "Permanent Redirect",
+            StatusCode::BadRequest => "Bad Request",
+            StatusCode::Unauthorized => "Unauthorized",
+            StatusCode::PaymentRequired => "Payment Required",
+            StatusCode::Forbidden => "Forbidden",
+            StatusCode::NotFound => "Not Found",
+            StatusCode::MethodNotAllowed => "Method Not Allowed",
+            StatusCode::NotAcceptable => "Not Acceptable",
+            StatusCode::ProxyAuthenticationRequired => "Proxy Authentication Required",
+            StatusCode::RequestTimeout => "Request Timeout",
+            StatusCode::Conflict => "Conflict",
+            StatusCode::Gone => "Gone",
+            StatusCode::LengthRequired => "Length Required",
+            StatusCode::PreconditionFailed => "Precondition Failed",
+            StatusCode::PayloadTooLarge => "Payload Too Large",
+            StatusCode::UriTooLong => "URI Too Long",
+            StatusCode::UnsupportedMediaType => "Unsupported Media Type",
+            StatusCode::RangeNotSatisfiable => "Range Not Satisfiable",
+            StatusCode::ExpectationFailed => "Expectation Failed",
+            StatusCode::ImATeapot => "I'm a teapot",
+            StatusCode::UnprocessableEntity => "Unprocessable Entity",
+            StatusCode::Locked => "Locked",
+            StatusCode::FailedDependency => "Failed Dependency",
+            StatusCode::UpgradeRequired => "Upgrade Required",
+            StatusCode::PreconditionRequired => "Precondition Required",
+            StatusCode::TooManyRequests => "Too Many Requests",
+            StatusCode::RequestHeaderFieldsTooLarge => "Request Header Fields Too Large",
+            StatusCode::UnavailableForLegalReasons => "Unavailable For Legal Reasons",
+            StatusCode::InternalServerError => "Internal Server Error",
+            StatusCode::NotImplemented => "Not Implemented",
+            StatusCode::BadGateway => "Bad Gateway",
+            StatusCode::ServiceUnavailable => "Service Unavailable",
+            StatusCode::GatewayTimeout => "Gateway Timeout",
+            StatusCode::HttpVersionNotSupported => "HTTP Version Not Supported",
+            StatusCode::VariantAlsoNegotiates => "Variant Also Negotiates",
+            StatusCode::InsufficientStorage => "Insufficient Storage",
+            StatusCode::LoopDetected => "Loop Detected",
+            StatusCode::NotExtended => "Not Extended",
+            StatusCode::NetworkAuthenticationRequired => "Network Authentication Required",
+            StatusCode::Custom(_) => "",
+        }
+    }
+
+    /// Return `true` if this is a 1xx status code.
+    pub fn is_informational(&self) -> bool {
+        self.as_u16() / 100 == 1
+    }
+
+    /// Return `true` if this is a 2xx status code.
+    pub fn is_success(&self) -> bool {
+        self.as_u16() / 100 == 2
+    }
+
+    /// Return `true` if this is a 3xx status code.
+    pub fn is_redirection(&self) -> bool {
+        self.as_u16() / 100 == 3
+    }
+
+    /// Return `true` if this is a 4xx status code.
+    pub fn is_client_error(&self) -> bool {
+        self.as_u16() / 100 == 4
+    }
+
+    /// Return `true` if this is a 5xx status code.
+    pub fn is_server_error(&self) -> bool {
+        self.as_u16() / 100 == 5
+    }
+}
+
+impl From<u16> for StatusCode {
+    fn from(code: u16) -> StatusCode {
+        match code {
+            100 => StatusCode::Continue,
+            101 => StatusCode::SwitchingProtocols,
+            102 => StatusCode::Processing,
+            200 => StatusCode::Ok,
+            201 => StatusCode::Created,
+            202 => StatusCode::Accepted,
+            203 => StatusCode::NonAuthoritativeInformation,
+            204 => StatusCode::NoContent,
+            205 => StatusCode::ResetContent,
+            206 => StatusCode::PartialContent,
+            207 => StatusCode::MultiStatus,
+            208 => StatusCode::AlreadyReported,
+            226 => StatusCode::ImUsed,
+            300 => StatusCode::MultipleChoices,
+            301 => StatusCode::MovedPermanently,
+            302 => StatusCode::Found,
+            303 => StatusCode::SeeOther,
+            304 => StatusCode::NotModified,
+            305 => StatusCode::UseProxy,
+            307 => StatusCode::TemporaryRedirect,
+            308 => StatusCode::PermanentRedirect,
+            400 => StatusCode::BadRequest,
+            401 => StatusCode::Unauthorized,
+            402 => StatusCode::PaymentRequired,
+            403 => StatusCode::Forbidden,
+            404 => StatusCode::NotFound,
+            405 => StatusCode::MethodNotAllowed,
+            406 => StatusCode::NotAcceptable,
+            407 => StatusCode::ProxyAuthenticationRequired,
+            408 => StatusCode::RequestTimeout,
+            409 => StatusCode::Conflict,
+            410 => StatusCode::Gone,
+            411 => StatusCode::LengthRequired,
+            412 => StatusCode::PreconditionFailed,
+            413 => StatusCode::PayloadTooLarge,
+            414 => StatusCode::UriTooLong,
+            415 => StatusCode::UnsupportedMediaType,
+            416 => StatusCode::RangeNotSatisfiable,
+            417 => StatusCode::ExpectationFailed,
+            418 => StatusCode::ImATeapot,
+            422 => StatusCode::UnprocessableEntity,
+            423 => StatusCode::Locked,
+            424 => StatusCode::FailedDependency,
+            426 => StatusCode::UpgradeRequired,
+            428 => StatusCode::PreconditionRequired,
+            429 => StatusCode::TooManyRequests,
+            431 => StatusCode::RequestHeaderFieldsTooLarge,
+            451 => StatusCode::UnavailableForLegalReasons,
+            500 => StatusCode::InternalServerError,
+            501 => StatusCode::NotImplemented,
+            502 => StatusCode::BadGateway,
+            503 => StatusCode::ServiceUnavailable,
+            504 => StatusCode::GatewayTimeout,
+            505 => StatusCode::HttpVersionNotSupported,
+            506 => StatusCode::VariantAlsoNegotiates,
+            507 => StatusCode::InsufficientStorage,
+            508 => StatusCode::LoopDetected,
+            510 => StatusCode::NotExtended,
+            511 => StatusCode::NetworkAuthenticationRequired,
+            other => StatusCode::Custom(other),
+        }
+    }
+}
+
+#[cfg(feature = "use-hyper")]
+impl From<hyper::status::StatusCode> for StatusCode {
+    fn from(code: hyper::status::StatusCode) -> StatusCode {
+        StatusCode::from(code.to_u16())
+    }
+}
+
+
 /// It represents the Server response received
 /// by the client after sending a HTTP request.
 pub trait HttpResponse {
@@ -105,6 +474,21 @@ pub trait HttpResponse {
     /// Response's body contain the data sent back from the server.
     fn body(&mut self) -> &mut Self::Body;
 
+    /// Typed view of `status`, for exhaustive, misspelling-proof
+    /// matching instead of comparing against a bare `u16`.
+    fn status_code(&self) -> StatusCode {
+        StatusCode::from(self.status())
+    }
+
+    /// Typed side-channel for this response, e.g. timing or trace data
+    /// a `Backend` wants to attach for `parse` to read back later.
+    /// `None` by default since most implementors — `hyper::client::Response`
+    /// included — have no field to back it with; override it, backed by a
+    /// real field, to make it `Some` (see `BackendResponse`).
+    fn extensions_mut(&mut self) -> Option<&mut Extensions> {
+        None
+    }
+
     /// Return `true` if the status code is 1xx, otherwise return `false`.
     fn is_1xx(&self) -> bool {
         self.status() / 100 == 1
@@ -151,17 +535,168 @@ impl HttpResponse for hyper::client::Response {
     fn body(&mut self) -> &mut hyper::client::Response {
         return self
     }
+
+    fn status_code(&self) -> StatusCode {
+        StatusCode::from(self.status.clone())
+    }
+}
+
+
+/// Like `HttpResponse`, but the body is consumed without blocking the
+/// calling thread: `Body` is read incrementally through `AsyncRead`
+/// instead of `io::Read`.
+#[cfg(feature = "use-futures")]
+pub trait AsyncHttpResponse {
+    type Body: AsyncRead;
+
+    /// Response's status code. It should be a integer
+    /// between 100 and 599.
+    fn status(&self) -> u16;
+
+    /// Reason-phrase that describes the status code.
+    /// i.e. 200 OK, 404 Not Found
+    fn reason(&self) -> &str;
+
+    /// Response's header. It contains metadata for the response.
+    fn headers(&self) -> Headers;
+
+    /// Response's body contains the data sent back from the server,
+    /// to be read incrementally rather than all at once.
+    fn body(&mut self) -> &mut Self::Body;
+
+    /// Return `true` if the status code is 1xx, otherwise return `false`.
+    fn is_1xx(&self) -> bool {
+        self.status() / 100 == 1
+    }
+
+    /// Return `true` if the status code is 2xx, otherwise return `false`.
+    fn is_2xx(&self) -> bool {
+        self.status() / 100 == 2
+    }
+
+    /// Return `true` if the status code is 3xx, otherwise return `false`.
+    fn is_3xx(&self) -> bool {
+        self.status() / 100 == 3
+    }
+
+    /// Return `true` if the status code is 4xx, otherwise return `false`.
+    fn is_4xx(&self) -> bool {
+        self.status() / 100 == 4
+    }
+
+    /// Return `true` if the status code is 5xx, otherwise return `false`.
+    fn is_5xx(&self) -> bool {
+        self.status() / 100 == 5
+    }
 }
 
 
 pub fn identity<T>(x: T) -> T { x }
 
+/// No-op closure for the `extensions` argument of `Api::transform`,
+/// for callers that don't need to stash anything.
+pub fn noop(_: &mut Extensions) {}
+
+
+/// Declares the `Content-Type` a request body requires, if any. The
+/// request pipeline (`Client::send`, `send_with_default`) consults
+/// this to set the header automatically unless the caller already
+/// supplied one.
+pub trait BodyMime {
+    fn mime(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+impl BodyMime for io::Empty {}
+impl BodyMime for io::Cursor<Vec<u8>> {}
+
+
+/// Request body that serializes `value` as JSON on first read, so an
+/// `Api::body` implementation doesn't have to hand-roll `io::Read` or
+/// remember to set `Content-Type` itself.
+#[cfg(feature = "use-serde-json")]
+pub struct Json<T: Serialize> {
+    value: T,
+    buffer: Option<io::Cursor<Vec<u8>>>,
+}
+
+#[cfg(feature = "use-serde-json")]
+impl<T: Serialize> Json<T> {
+    pub fn new(value: T) -> Json<T> {
+        Json { value: value, buffer: None }
+    }
+
+    fn buffer(&mut self) -> io::Result<&mut io::Cursor<Vec<u8>>> {
+        if self.buffer.is_none() {
+            let bytes = serde_json::to_vec(&self.value)
+                .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+            self.buffer = Some(io::Cursor::new(bytes));
+        }
+        Ok(self.buffer.as_mut().unwrap())
+    }
+}
+
+#[cfg(feature = "use-serde-json")]
+impl<T: Serialize> io::Read for Json<T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.buffer()?.read(buf)
+    }
+}
+
+#[cfg(feature = "use-serde-json")]
+impl<T: Serialize> BodyMime for Json<T> {
+    fn mime(&self) -> Option<&'static str> {
+        Some("application/json")
+    }
+}
+
+
+/// Request body that serializes `value` as `application/x-www-form-urlencoded`
+/// on first read, so an `Api::body` implementation doesn't have to
+/// hand-roll `io::Read` or remember to set `Content-Type` itself.
+#[cfg(feature = "use-serde-form")]
+pub struct Form<T: Serialize> {
+    value: T,
+    buffer: Option<io::Cursor<Vec<u8>>>,
+}
+
+#[cfg(feature = "use-serde-form")]
+impl<T: Serialize> Form<T> {
+    pub fn new(value: T) -> Form<T> {
+        Form { value: value, buffer: None }
+    }
+
+    fn buffer(&mut self) -> io::Result<&mut io::Cursor<Vec<u8>>> {
+        if self.buffer.is_none() {
+            let encoded = serde_urlencoded::to_string(&self.value)
+                .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+            self.buffer = Some(io::Cursor::new(encoded.into_bytes()));
+        }
+        Ok(self.buffer.as_mut().unwrap())
+    }
+}
+
+#[cfg(feature = "use-serde-form")]
+impl<T: Serialize> io::Read for Form<T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.buffer()?.read(buf)
+    }
+}
+
+#[cfg(feature = "use-serde-form")]
+impl<T: Serialize> BodyMime for Form<T> {
+    fn mime(&self) -> Option<&'static str> {
+        Some("application/x-www-form-urlencoded")
+    }
+}
+
 
 /// `Api` represents a HTTP API exposing all the request parameters
 /// and a function to parse the HTTP response.
 pub trait Api {
     type Reply;
-    type Body: io::Read;
+    type Body: io::Read + BodyMime;
     type Error;
 
     /// Return the HTTP method used by this API.
@@ -181,18 +716,43 @@ pub trait Api {
     /// `std::io::Empty`.
     fn body(&self) -> Self::Body;
 
+    /// Typed side-channel for this request, e.g. a request ID, a
+    /// deadline, or auth context stashed by middleware-style code
+    /// without encoding it into headers. Defaults to an empty map.
+    fn extensions(&self) -> &Extensions {
+        static EMPTY: OnceLock<Extensions> = OnceLock::new();
+        EMPTY.get_or_init(Extensions::new)
+    }
+
     /// Parse the HTTP response, received from the actual client,
     /// into the type `Reply`.
     fn parse<Resp>(&self, &mut Resp) -> Result<Self::Reply, Self::Error> where Resp: HttpResponse;
 
-    fn transform<H, Q, B>(&self, h: H, q: Q, b: B) -> Transform<Self, H, Q, B>
-        where Self: Sized
+    /// Snapshot this request's method, path, query, headers and body
+    /// into a `Frozen` value that can be sent more than once, e.g. by
+    /// `Client::send_with_retry`. The body is read into memory once so
+    /// it can be replayed on every attempt.
+    fn freeze(self) -> io::Result<Frozen<Self>> where Self: Sized {
+        Frozen::new(self)
+    }
+
+    /// Wrap this `Api` in a `Transform` that passes its headers, query
+    /// and body through `h`, `q` and `b`. `x` is a closure run once,
+    /// against an empty `Extensions`, to populate the extensions the
+    /// returned `Transform` exposes. Falls back to the wrapped `Api`'s own
+    /// extensions if `x` leaves the map empty.
+    fn transform<H, Q, B, X>(&self, h: H, q: Q, b: B, x: X) -> Transform<Self, H, Q, B>
+        where Self: Sized, X: FnOnce(&mut Extensions)
     {
+        let mut extensions = Extensions::new();
+        x(&mut extensions);
+
         Transform {
             api: self,
             h: h,
             q: q,
             b: b,
+            extensions: extensions,
         }
     }
 }
@@ -203,7 +763,8 @@ pub struct Transform<'a, A: 'a, H, Q, B>
     api: &'a A,
     h: H,
     q: Q,
-    b: B
+    b: B,
+    extensions: Extensions,
 }
 
 impl<'a, A, H, Q, B, NewBody> Api for Transform<'a, A, H, Q, B>
@@ -211,7 +772,7 @@ impl<'a, A, H, Q, B, NewBody> Api for Transform<'a, A, H, Q, B>
           H: Fn(Headers) -> Headers,
           Q: Fn(Query) -> Query,
           B: Fn(A::Body) -> NewBody,
-          NewBody: io::Read
+          NewBody: io::Read + BodyMime
 {
     type Reply = A::Reply;
     type Body = NewBody;
@@ -237,6 +798,148 @@ impl<'a, A, H, Q, B, NewBody> Api for Transform<'a, A, H, Q, B>
         (self.b)(self.api.body())
     }
 
+    fn extensions(&self) -> &Extensions {
+        if self.extensions.is_empty() {
+            self.api.extensions()
+        } else {
+            &self.extensions
+        }
+    }
+
+    fn parse<Resp>(&self, resp: &mut Resp) -> Result<Self::Reply, Self::Error>
+        where Resp: HttpResponse
+    {
+        self.api.parse(resp)
+    }
+}
+
+
+/// Opt-in extension of `Api` for implementors whose response can also be
+/// parsed without blocking the calling thread. Turning on `use-futures`
+/// only requires this trait from the `Api`s that want the non-blocking
+/// path; every other `Api` keeps compiling unchanged.
+#[cfg(feature = "use-futures")]
+pub trait AsyncApi: Api {
+    /// Like `parse`, but consumes an `AsyncHttpResponse` and returns a
+    /// future so the body can be read incrementally instead of blocking
+    /// the calling thread until it is fully available.
+    fn parse_async<Resp>(&self, resp: &mut Resp)
+        -> Box<Future<Item = Self::Reply, Error = Self::Error>>
+        where Resp: AsyncHttpResponse;
+}
+
+#[cfg(feature = "use-futures")]
+impl<'a, A, H, Q, B, NewBody> AsyncApi for Transform<'a, A, H, Q, B>
+    where A: AsyncApi,
+          H: Fn(Headers) -> Headers,
+          Q: Fn(Query) -> Query,
+          B: Fn(A::Body) -> NewBody,
+          NewBody: io::Read + BodyMime
+{
+    fn parse_async<Resp>(&self, resp: &mut Resp)
+        -> Box<Future<Item = Self::Reply, Error = Self::Error>>
+        where Resp: AsyncHttpResponse
+    {
+        self.api.parse_async(resp)
+    }
+}
+
+
+/// Body type produced by a `Frozen` request: the buffered bytes,
+/// replayed via `io::Read`, paired with whatever `Content-Type` the
+/// original body declared (if any) so that information survives the
+/// freeze.
+#[derive(Clone)]
+pub struct FrozenBody {
+    cursor: io::Cursor<Vec<u8>>,
+    mime: Option<&'static str>,
+}
+
+impl io::Read for FrozenBody {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.cursor.read(buf)
+    }
+}
+
+impl BodyMime for FrozenBody {
+    fn mime(&self) -> Option<&'static str> {
+        self.mime
+    }
+}
+
+
+/// A request whose method, path, query, headers and body have been
+/// captured up front, so it can be sent more than once. `Api::body` is
+/// normally single-use (an `io::Read` consumed in place); `Frozen`
+/// reads it once into a buffer and replays that buffer on every send.
+#[derive(Clone)]
+pub struct Frozen<A> {
+    api: A,
+    method: Method,
+    path: String,
+    query: Query<'static>,
+    headers: Headers,
+    body: Vec<u8>,
+    mime: Option<&'static str>,
+}
+
+impl<A: Api> Frozen<A> {
+    fn new(api: A) -> io::Result<Frozen<A>> {
+        let method = api.method();
+        let path = api.path();
+        let query = api.query();
+        let headers = api.headers();
+
+        let mut raw_body = api.body();
+        let mime = raw_body.mime();
+
+        let mut body = Vec::new();
+        raw_body.read_to_end(&mut body)?;
+
+        Ok(Frozen {
+            api: api,
+            method: method,
+            path: path,
+            query: query,
+            headers: headers,
+            body: body,
+            mime: mime,
+        })
+    }
+}
+
+impl<A: Api> Api for Frozen<A> {
+    type Reply = A::Reply;
+    type Body = FrozenBody;
+    type Error = A::Error;
+
+    fn method(&self) -> Method {
+        self.method.clone()
+    }
+
+    fn path(&self) -> String {
+        self.path.clone()
+    }
+
+    fn query(&self) -> Query {
+        self.query.clone()
+    }
+
+    fn headers(&self) -> Headers {
+        self.headers.clone()
+    }
+
+    fn body(&self) -> Self::Body {
+        FrozenBody {
+            cursor: io::Cursor::new(self.body.clone()),
+            mime: self.mime,
+        }
+    }
+
+    fn extensions(&self) -> &Extensions {
+        self.api.extensions()
+    }
+
     fn parse<Resp>(&self, resp: &mut Resp) -> Result<Self::Reply, Self::Error>
         where Resp: HttpResponse
     {
@@ -244,15 +947,80 @@ impl<'a, A, H, Q, B, NewBody> Api for Transform<'a, A, H, Q, B>
     }
 }
 
+#[cfg(feature = "use-futures")]
+impl<A: AsyncApi> AsyncApi for Frozen<A> {
+    fn parse_async<Resp>(&self, resp: &mut Resp)
+        -> Box<Future<Item = Self::Reply, Error = Self::Error>>
+        where Resp: AsyncHttpResponse
+    {
+        self.api.parse_async(resp)
+    }
+}
+
+
+/// Controls how `Client::send_with_retry` re-issues a `Frozen` request.
+pub struct RetryPolicy<S, AErr> {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub backoff_factor: f64,
+    pub should_retry: Box<Fn(&SendError<S, AErr>) -> bool>,
+}
+
+impl<S, AErr> RetryPolicy<S, AErr> {
+    pub fn new(max_attempts: u32, base_delay: Duration, backoff_factor: f64, should_retry: Box<Fn(&SendError<S, AErr>) -> bool>) -> RetryPolicy<S, AErr> {
+        RetryPolicy {
+            max_attempts: max_attempts,
+            base_delay: base_delay,
+            backoff_factor: backoff_factor,
+            should_retry: should_retry,
+        }
+    }
+
+    /// Delay to sleep after the attempt numbered `attempt` (0-based) has
+    /// failed, before the next one: `base_delay` grown by `backoff_factor`
+    /// once for each attempt before it, so the first retry waits `base_delay`.
+    fn delay_for(&self, attempt: u32) -> Duration {
+        let base_millis = self.base_delay.as_secs() as f64 * 1000.0
+            + self.base_delay.subsec_nanos() as f64 / 1_000_000.0;
+        let millis = base_millis * self.backoff_factor.powi(attempt as i32);
+        Duration::from_millis(millis as u64)
+    }
+}
+
 
+/// `Api(status, err)` carries the response status alongside the parse
+/// error, so `RetryPolicy::should_retry` can decide based on it.
 #[derive(Debug)]
-pub enum SendError<S, A> {
+pub enum SendError<S, AErr> {
     Client(S),
-    Api(A)
+    Api(StatusCode, AErr)
 }
 
 pub trait Client<A: Api, E> {
     fn send(&mut self, url: &str, req: A) -> Result<A::Reply, SendError<E, A::Error>>;
+
+    /// Re-issue `frozen` (replaying its buffered body each time) until
+    /// it succeeds, `policy`'s attempts are exhausted, or
+    /// `policy.should_retry` rejects the error, sleeping for
+    /// an exponentially increasing delay between attempts.
+    fn send_with_retry(&mut self, url: &str, frozen: Frozen<A>, policy: RetryPolicy<E, A::Error>)
+        -> Result<A::Reply, SendError<E, A::Error>>
+        where Self: Client<Frozen<A>, E>, A: Clone
+    {
+        let mut attempt = 0;
+        loop {
+            match Client::send(self, url, frozen.clone()) {
+                Ok(reply) => return Ok(reply),
+                Err(err) => {
+                    attempt += 1;
+                    if attempt >= policy.max_attempts || !(policy.should_retry)(&err) {
+                        return Err(err);
+                    }
+                    thread::sleep(policy.delay_for(attempt - 1));
+                }
+            }
+        }
+    }
 }
 
 
@@ -269,6 +1037,7 @@ impl<A: Api> Client<A, hyper::Error> for hyper::Client {
             .join(req.path().as_ref())
             .map_err(|e| SendError::Client(hyper::Error::Uri(e)))?;
         let mut body = req.body();
+        let mime = body.mime();
         let body = hyper::client::Body::ChunkedBody(&mut body);
 
         {
@@ -278,8 +1047,15 @@ impl<A: Api> Client<A, hyper::Error> for hyper::Client {
             }
         }
 
+        let mut req_headers = req.headers();
+        if let Some(mime) = mime {
+            if !req_headers.contains_key("Content-Type") {
+                req_headers.insert("Content-Type".to_string(), vec![mime.to_string()]);
+            }
+        }
+
         let mut headers = hyper::header::Headers::new();
-        for (name, value) in req.headers() {
+        for (name, value) in req_headers {
             headers.set_raw(
                 name,
                 value.iter().map(|v| v.clone().into_bytes()).collect()
@@ -292,15 +1068,127 @@ impl<A: Api> Client<A, hyper::Error> for hyper::Client {
             .send()
             .map_err(|e| SendError::Client(e))?;
 
+        let status = resp.status_code();
         req.parse(&mut resp)
-            .map_err(|e| SendError::Api(e))
+            .map_err(|e| SendError::Api(status, e))
+    }
+}
+
+
+/// Like `Client`, but `send` returns a future that resolves once the
+/// response has arrived instead of blocking the calling thread. This
+/// lets the crate be used from async runtimes without dedicating a
+/// thread to each in-flight request.
+#[cfg(feature = "use-futures")]
+pub trait AsyncClient<A: Api, E> {
+    type Future: Future<Item = A::Reply, Error = SendError<E, A::Error>>;
+
+    fn send(&mut self, url: &str, req: A) -> Self::Future;
+}
+
+
+/// Response produced by a `Backend`. It owns its body as a boxed
+/// `io::Read` so that any concrete client's response type can stand
+/// in behind `set_backend`/`note_backend`.
+pub struct BackendResponse {
+    pub status: u16,
+    pub reason: String,
+    pub headers: Headers,
+    pub body: Box<io::Read>,
+    pub extensions: Extensions,
+}
+
+impl HttpResponse for BackendResponse {
+    type Body = Box<io::Read>;
+
+    fn status(&self) -> u16 {
+        self.status
+    }
+
+    fn reason(&self) -> &str {
+        self.reason.as_ref()
+    }
+
+    fn headers(&self) -> Headers {
+        self.headers.clone()
+    }
+
+    fn body(&mut self) -> &mut Self::Body {
+        &mut self.body
+    }
+
+    fn extensions_mut(&mut self) -> Option<&mut Extensions> {
+        Some(&mut self.extensions)
+    }
+}
+
+/// Error produced by a `Backend` while dispatching a request, e.g. a
+/// connection failure or a timeout.
+#[derive(Debug)]
+pub struct BackendError(pub String);
+
+/// A pluggable HTTP backend, registered with `set_backend` or
+/// `note_backend` so `Api` implementors never name a concrete client type.
+pub trait Backend: Send + Sync {
+    fn send(&self, method: Method, path: &str, query: &Query, headers: &Headers, body: &mut io::Read)
+        -> Result<BackendResponse, BackendError>;
+}
+
+static BACKEND: RwLock<Option<Box<Backend>>> = RwLock::new(None);
+
+thread_local! {
+    static TEST_BACKEND: RefCell<Option<Box<Backend>>> = RefCell::new(None);
+}
+
+/// Register `backend` as the process-wide default. Later calls replace
+/// whatever was registered before.
+pub fn set_backend<B: Backend + 'static>(backend: B) {
+    let mut slot = BACKEND.write().unwrap();
+    *slot = Some(Box::new(backend));
+}
+
+/// Register `backend` for the current thread only, overriding the
+/// process-wide default for calls made from this thread. Intended for
+/// tests, so an in-memory mock backend never has to be shared across
+/// (and race with) tests running on other threads.
+pub fn note_backend<B: Backend + 'static>(backend: B) {
+    TEST_BACKEND.with(|slot| *slot.borrow_mut() = Some(Box::new(backend)));
+}
+
+/// Send `req` through whichever `Backend` is currently in effect: the
+/// thread-local one installed by `note_backend`, if any, otherwise the
+/// process-wide one installed by `set_backend`. `Api` code that calls
+/// this never has to name a concrete client type.
+pub fn send_with_default<A: Api>(req: A) -> Result<A::Reply, SendError<BackendError, A::Error>> {
+    let query = req.query();
+    let mut headers = req.headers();
+    let mut body = req.body();
+    if let Some(mime) = body.mime() {
+        if !headers.contains_key("Content-Type") {
+            headers.insert("Content-Type".to_string(), vec![mime.to_string()]);
+        }
     }
+
+    let mut resp = TEST_BACKEND.with(|slot| {
+        slot.borrow().as_ref().map(|backend| {
+            backend.send(req.method(), req.path().as_ref(), &query, &headers, &mut body)
+        })
+    }).unwrap_or_else(|| {
+        let backend = BACKEND.read().unwrap();
+        let backend = backend.as_ref().expect("no backend registered; call `set_backend` or `note_backend` first");
+        backend.send(req.method(), req.path().as_ref(), &query, &headers, &mut body)
+    }).map_err(|e| SendError::Client(e))?;
+
+    let status = resp.status_code();
+    req.parse(&mut resp)
+        .map_err(|e| SendError::Api(status, e))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[derive(Clone)]
     struct TestApi {
         n: u8,
     }
@@ -339,7 +1227,7 @@ mod tests {
     fn transform_request_identity() {
         let api = TestApi::new(10);
 
-        let t_api = api.transform(identity, identity, identity);
+        let t_api = api.transform(identity, identity, identity, noop);
 
         assert_eq!(api.headers(), t_api.headers());
         assert_eq!(api.query(), t_api.query());
@@ -352,7 +1240,8 @@ mod tests {
         let t_api = api.transform(
             |mut h: Headers| { h.insert("X-Request-ID".to_string(), vec!["abcdef123".to_string()]); h },
             |mut q: Query| { q.push(("foo".to_string(), "bar".to_string())); q },
-            identity
+            identity,
+            noop
         );
 
         let mut expected_headers = Headers::new();
@@ -374,13 +1263,15 @@ mod tests {
         let t1_api = api.transform(
             |mut h: Headers| { h.insert("X-Request-ID".to_string(), vec!["abcdef123".to_string()]); h },
             |mut q: Query| { q.push(("foo".to_string(), "bar".to_string())); q },
-            identity::<io::Empty>
+            identity::<io::Empty>,
+            noop
         );
 
         let t2_api = api.transform(
             |mut h: Headers| { h.insert("X-Request-ID".to_string(), vec!["321fedcba".to_string()]); h },
             identity::<Query>,
-            identity::<io::Empty>
+            identity::<io::Empty>,
+            noop
         );
 
         // check t1
@@ -402,4 +1293,299 @@ mod tests {
         assert_eq!(expected_headers, t2_api.headers());
         assert_eq!(api.query(), t2_api.query());
     }
+
+    struct MockBackend;
+
+    impl Backend for MockBackend {
+        fn send(&self, _method: Method, path: &str, _query: &Query, _headers: &Headers, _body: &mut io::Read)
+            -> Result<BackendResponse, BackendError>
+        {
+            Ok(BackendResponse {
+                status: 200,
+                reason: "OK".to_string(),
+                headers: Headers::new(),
+                body: Box::new(io::Cursor::new(path.as_bytes().to_vec())),
+                extensions: Extensions::new(),
+            })
+        }
+    }
+
+    #[test]
+    fn send_with_default_uses_thread_local_backend() {
+        note_backend(MockBackend);
+
+        let reply = send_with_default(TestApi::new(10)).unwrap();
+
+        assert_eq!(reply, Vec::<u8>::new());
+    }
+
+    struct ApiWithBody {
+        body: Vec<u8>,
+    }
+
+    impl Api for ApiWithBody {
+        type Reply = Vec<u8>;
+        type Body = io::Cursor<Vec<u8>>;
+        type Error = ();
+
+        fn method(&self) -> Method { Method::Post }
+
+        fn path(&self) -> String { "/top".to_string() }
+
+        fn query(&self) -> Query { Query::new() }
+
+        fn headers(&self) -> Headers { Headers::new() }
+
+        fn body(&self) -> Self::Body { io::Cursor::new(self.body.clone()) }
+
+        fn parse<Resp>(&self, _resp: &mut Resp) -> Result<Self::Reply, Self::Error>
+            where Resp: HttpResponse
+        {
+            Ok(vec![])
+        }
+    }
+
+    #[test]
+    fn frozen_replays_buffered_body() {
+        let frozen = ApiWithBody { body: b"hello world".to_vec() }.freeze().unwrap();
+
+        let mut first = Vec::new();
+        frozen.body().read_to_end(&mut first).unwrap();
+
+        let mut second = Vec::new();
+        frozen.body().read_to_end(&mut second).unwrap();
+
+        assert_eq!(b"hello world".to_vec(), first);
+        assert_eq!(first, second);
+    }
+
+    struct CountingClient {
+        failures_left: u32,
+    }
+
+    // `send_with_retry` only ever calls `Client::send` with a `Frozen<TestApi>`,
+    // but its `where Self: Client<Frozen<A>, E>` bound still leaves `A` itself
+    // ambiguous to type inference unless a `Client<TestApi, _>` impl is also in
+    // scope to pin `A = TestApi`. This impl is never actually called.
+    impl Client<TestApi, ()> for CountingClient {
+        fn send(&mut self, _url: &str, _req: TestApi) -> Result<Vec<u8>, SendError<(), ()>> {
+            unreachable!("this test only ever sends frozen requests")
+        }
+    }
+
+    impl Client<Frozen<TestApi>, ()> for CountingClient {
+        fn send(&mut self, _url: &str, _req: Frozen<TestApi>) -> Result<Vec<u8>, SendError<(), ()>> {
+            if self.failures_left > 0 {
+                self.failures_left -= 1;
+                Err(SendError::Client(()))
+            } else {
+                Ok(vec![])
+            }
+        }
+    }
+
+    #[test]
+    fn send_with_retry_retries_until_success() {
+        let frozen = TestApi::new(10).freeze().unwrap();
+        let policy = RetryPolicy::new(5, Duration::from_millis(0), 1.0, Box::new(|_| true));
+
+        let mut client = CountingClient { failures_left: 2 };
+        let reply = client.send_with_retry("http://example.com", frozen, policy).unwrap();
+
+        assert_eq!(reply, Vec::<u8>::new());
+    }
+
+    struct StatusClient {
+        statuses: Vec<StatusCode>,
+    }
+
+    // Same type-inference wrinkle as `CountingClient` above.
+    impl Client<TestApi, ()> for StatusClient {
+        fn send(&mut self, _url: &str, _req: TestApi) -> Result<Vec<u8>, SendError<(), ()>> {
+            unreachable!("this test only ever sends frozen requests")
+        }
+    }
+
+    impl Client<Frozen<TestApi>, ()> for StatusClient {
+        fn send(&mut self, _url: &str, _req: Frozen<TestApi>) -> Result<Vec<u8>, SendError<(), ()>> {
+            let status = self.statuses.remove(0);
+            if status.is_success() {
+                Ok(vec![])
+            } else {
+                Err(SendError::Api(status, ()))
+            }
+        }
+    }
+
+    #[test]
+    fn send_with_retry_only_retries_on_server_error_status() {
+        let frozen = TestApi::new(10).freeze().unwrap();
+        let policy = RetryPolicy::new(
+            5,
+            Duration::from_millis(0),
+            1.0,
+            Box::new(|err| match *err {
+                SendError::Api(status, _) => status.is_server_error(),
+                SendError::Client(_) => false,
+            })
+        );
+
+        let mut client = StatusClient {
+            statuses: vec![StatusCode::ServiceUnavailable, StatusCode::ServiceUnavailable, StatusCode::Ok]
+        };
+        let reply = client.send_with_retry("http://example.com", frozen.clone(), policy).unwrap();
+        assert_eq!(reply, Vec::<u8>::new());
+
+        let policy = RetryPolicy::new(
+            5,
+            Duration::from_millis(0),
+            1.0,
+            Box::new(|err| match *err {
+                SendError::Api(status, _) => status.is_server_error(),
+                SendError::Client(_) => false,
+            })
+        );
+
+        let mut client = StatusClient { statuses: vec![StatusCode::BadRequest, StatusCode::Ok] };
+        let result = client.send_with_retry("http://example.com", frozen, policy);
+        assert!(match result {
+            Err(SendError::Api(StatusCode::BadRequest, ())) => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn status_code_round_trips_through_u16() {
+        let code = StatusCode::from(404);
+
+        assert_eq!(StatusCode::NotFound, code);
+        assert_eq!(404, code.as_u16());
+        assert_eq!("Not Found", code.canonical_reason());
+        assert!(code.is_client_error());
+        assert!(!code.is_success());
+    }
+
+    #[test]
+    fn status_code_falls_back_to_custom() {
+        let code = StatusCode::from(599);
+
+        assert_eq!(StatusCode::Custom(599), code);
+        assert_eq!(599, code.as_u16());
+        assert!(code.is_server_error());
+    }
+
+    #[test]
+    fn extensions_stash_and_retrieve_typed_values() {
+        let mut extensions = Extensions::new();
+
+        assert_eq!(None, extensions.insert(7u32));
+        assert_eq!(Some(&7u32), extensions.get::<u32>());
+        assert_eq!(None, extensions.get::<String>());
+
+        assert_eq!(Some(7u32), extensions.remove::<u32>());
+        assert_eq!(None, extensions.get::<u32>());
+    }
+
+    #[test]
+    fn transform_populates_its_own_extensions() {
+        let api = TestApi::new(10);
+
+        assert_eq!(None, api.extensions().get::<u32>());
+
+        let t_api = api.transform(identity, identity, identity, |ext: &mut Extensions| {
+            ext.insert(42u32);
+        });
+
+        assert_eq!(Some(&42u32), t_api.extensions().get::<u32>());
+    }
+
+    struct ApiWithExtensions {
+        extensions: Extensions,
+    }
+
+    impl Api for ApiWithExtensions {
+        type Reply = Vec<u8>;
+        type Body = io::Empty;
+        type Error = ();
+
+        fn method(&self) -> Method { Method::Get }
+
+        fn path(&self) -> String { "/top".to_string() }
+
+        fn query(&self) -> Query { Query::new() }
+
+        fn headers(&self) -> Headers { Headers::new() }
+
+        fn body(&self) -> Self::Body { io::empty() }
+
+        fn extensions(&self) -> &Extensions {
+            &self.extensions
+        }
+
+        fn parse<Resp>(&self, _resp: &mut Resp) -> Result<Self::Reply, Self::Error>
+            where Resp: HttpResponse
+        {
+            Ok(vec![])
+        }
+    }
+
+    #[test]
+    fn frozen_forwards_wrapped_apis_extensions() {
+        let mut extensions = Extensions::new();
+        extensions.insert(7u32);
+        let api = ApiWithExtensions { extensions: extensions };
+
+        let frozen = api.freeze().unwrap();
+
+        assert_eq!(Some(&7u32), frozen.extensions().get::<u32>());
+    }
+
+    #[test]
+    fn transform_forwards_wrapped_apis_extensions() {
+        let mut extensions = Extensions::new();
+        extensions.insert(7u32);
+        let api = ApiWithExtensions { extensions: extensions };
+
+        let t_api = api.transform(identity, identity, identity, |_ext: &mut Extensions| {});
+
+        assert_eq!(Some(&7u32), t_api.extensions().get::<u32>());
+    }
+
+    #[cfg(feature = "use-serde-json")]
+    #[derive(Serialize)]
+    struct Ping {
+        n: u8,
+    }
+
+    #[cfg(feature = "use-serde-json")]
+    #[test]
+    fn json_body_serializes_and_declares_its_mime() {
+        let mut body = Json::new(Ping { n: 10 });
+
+        assert_eq!(Some("application/json"), body.mime());
+
+        let mut bytes = Vec::new();
+        body.read_to_end(&mut bytes).unwrap();
+
+        assert_eq!(b"{\"n\":10}".to_vec(), bytes);
+    }
+
+    #[cfg(feature = "use-serde-form")]
+    #[derive(Serialize)]
+    struct FormPing {
+        n: u8,
+    }
+
+    #[cfg(feature = "use-serde-form")]
+    #[test]
+    fn form_body_serializes_and_declares_its_mime() {
+        let mut body = Form::new(FormPing { n: 10 });
+
+        assert_eq!(Some("application/x-www-form-urlencoded"), body.mime());
+
+        let mut bytes = Vec::new();
+        body.read_to_end(&mut bytes).unwrap();
+
+        assert_eq!(b"n=10".to_vec(), bytes);
+    }
 }